@@ -5,13 +5,127 @@
 //!
 //! ## Warning
 //!
-//! This is inherently unsafe if the `JoinGuard` is allowed to leak without being dropped.
-//! See #24292 for more details.
+//! `scoped`/`Builder::scoped` are inherently unsafe if the returned `JoinGuard` is allowed to
+//! leak without being dropped. See #24292 for more details. `scope` does not have this hazard,
+//! since it does not return until every thread spawned within it has finished.
 
+use std::io;
 use std::marker::PhantomData;
-use std::thread::{spawn, JoinHandle, Thread};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle, Thread};
 use std::mem::{transmute, forget};
 
+/// Thread configuration for spawning a scoped thread, mirroring `std::thread::Builder`
+///
+/// Unlike `std::thread::Builder::spawn`, `Builder::scoped` does not require its closure to be
+/// `'static`, returning a `JoinGuard` borrowed for the given lifetime instead.
+#[derive(Debug)]
+pub struct Builder {
+    inner: thread::Builder,
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+impl Builder {
+    /// Generates the base configuration for spawning a scoped thread, from which configuration
+    /// methods can be chained
+    pub fn new() -> Builder {
+        Builder {
+            inner: thread::Builder::new(),
+        }
+    }
+
+    /// Names the thread-to-be, for use in panic messages and the OS-level thread name
+    pub fn name(mut self, name: String) -> Builder {
+        self.inner = self.inner.name(name);
+        self
+    }
+
+    /// Sets the size of the stack for the new thread
+    pub fn stack_size(mut self, size: usize) -> Builder {
+        self.inner = self.inner.stack_size(size);
+        self
+    }
+
+    /// Spawns a new scoped thread, and returns a `JoinGuard` for it
+    ///
+    /// Unlike `scoped`, this surfaces any OS-level failure to create the thread as an
+    /// `io::Error` rather than panicking
+    pub fn scoped<'a, T, F>(self, f: F) -> io::Result<JoinGuard<'a, T>> where
+        T: Send + 'static, F: FnOnce() -> T, F: Send + 'a
+    {
+        struct Sendable<T>(T);
+
+        unsafe impl<T> Send for Sendable<T> { }
+
+        unsafe {
+            let mut b = Box::new(f);
+            let b_ptr = Sendable(&mut *b as *mut F as *mut ());
+            forget(b);
+
+            self.inner.spawn(move || {
+                transmute::<_, Box<F>>(b_ptr.0 as *mut F)()
+            }).map(|inner| JoinGuard {
+                inner: Some(inner),
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    /// Spawns a new thread within `scope` using this `Builder`'s configuration, returning a
+    /// `ScopedJoinHandle` for it
+    ///
+    /// Unlike `Scope::spawn`, this surfaces any OS-level failure to create the thread as an
+    /// `io::Error` rather than panicking, and propagates the configured name and stack size to
+    /// the native OS thread the same way `Builder::scoped` does.
+    pub fn spawn_scoped<'scope, 'env, T, F>(self, scope: &'scope Scope<'scope, 'env>, f: F) -> io::Result<ScopedJoinHandle<'scope, T>> where
+        T: Send + 'static, F: FnOnce() -> T, F: Send + 'scope
+    {
+        struct Sendable<T>(T);
+
+        unsafe impl<T> Send for Sendable<T> { }
+
+        scope.data.increment_num_running_threads();
+        let data = scope.data.clone();
+
+        let result = unsafe {
+            let mut b = Box::new(f);
+            let b_ptr = Sendable(&mut *b as *mut F as *mut ());
+            forget(b);
+
+            self.inner.spawn(move || {
+                let f = transmute::<_, Box<F>>(b_ptr.0 as *mut F);
+                let result = catch_unwind(AssertUnwindSafe(f));
+                if result.is_err() {
+                    data.a_thread_panicked.store(true, Ordering::Relaxed);
+                }
+                data.decrement_num_running_threads();
+                match result {
+                    Ok(result) => result,
+                    Err(payload) => resume_unwind(payload),
+                }
+            })
+        };
+
+        match result {
+            Ok(inner) => Ok(ScopedJoinHandle {
+                inner: inner,
+                _marker: PhantomData,
+            }),
+            Err(e) => {
+                scope.data.decrement_num_running_threads();
+                Err(e)
+            },
+        }
+    }
+}
+
 /// A RAII guard for that joins a scoped thread upon drop
 ///
 /// # Panics
@@ -36,12 +150,22 @@ impl<'a, T: Send + 'a> JoinGuard<'a, T> {
     /// # Panics
     ///
     /// `join()` will panic if the owned thread panics
-    pub fn join(mut self) -> T {
-        match self.inner.take().unwrap().join() {
+    pub fn join(self) -> T {
+        let thread = self.thread().clone();
+        match self.try_join() {
             Ok(res) => res,
-            Err(_) => panic!("child thread {:?} panicked", self.thread()),
+            Err(_) => panic!("child thread {:?} panicked", thread),
         }
     }
+
+    /// Joins the guarded thread, returning the child's panic payload rather than panicking
+    ///
+    /// On success, returns `Ok(value)` with the child's result. If the child panicked, returns
+    /// `Err` with the panic payload instead of re-panicking, so the caller can inspect or
+    /// re-raise it on their own terms.
+    pub fn try_join(mut self) -> thread::Result<T> {
+        self.inner.take().unwrap().join()
+    }
 }
 
 /// Detaches a child thread from its guard
@@ -69,30 +193,142 @@ impl<T: Send + 'static> ScopedDetach for ::std::thread::JoinGuard<'static, T> {
 
 impl<'a, T: Send + 'a> Drop for JoinGuard<'a, T> {
     fn drop(&mut self) {
-        self.inner.take().map(|v| if v.join().is_err() {
-            panic!("child thread {:?} panicked", self.thread());
-        });
+        if let Some(inner) = self.inner.take() {
+            let thread = inner.thread().clone();
+            if inner.join().is_err() {
+                panic!("child thread {:?} panicked", thread);
+            }
+        }
     }
 }
 
 /// Spawns a new scoped thread
+///
+/// # Panics
+///
+/// Panics if the OS fails to create a thread; see `Builder::scoped` for a non-panicking
+/// alternative
 pub fn scoped<'a, T, F>(f: F) -> JoinGuard<'a, T> where
     T: Send + 'static, F: FnOnce() -> T, F: Send + 'a
 {
-    struct Sendable<T>(T);
+    Builder::new().scoped(f).unwrap()
+}
 
-    unsafe impl<T> Send for Sendable<T> { }
+/// Data shared between a `Scope` and every `ScopedJoinHandle` spawned from it
+struct ScopeData {
+    num_running_threads: AtomicUsize,
+    a_thread_panicked: AtomicBool,
+    main_thread: Thread,
+}
 
-    unsafe {
-        let mut b = Box::new(f);
-        let b_ptr = Sendable(&mut *b as *mut F as *mut ());
-        forget(b);
+impl ScopeData {
+    fn increment_num_running_threads(&self) {
+        if self.num_running_threads.fetch_add(1, Ordering::Relaxed) > usize::MAX / 2 {
+            self.decrement_num_running_threads();
+            panic!("too many running threads in thread scope");
+        }
+    }
 
-        JoinGuard {
-            inner: Some(spawn(move || {
-                transmute::<_, Box<F>>(b_ptr.0 as *mut F)()
-            })),
-            _marker: PhantomData,
+    fn decrement_num_running_threads(&self) {
+        if self.num_running_threads.fetch_sub(1, Ordering::Release) == 1 {
+            self.main_thread.unpark();
+        }
+    }
+}
+
+/// A scope for spawning scoped threads that may borrow data for the `'env` lifetime
+///
+/// See `scope` for more details.
+pub struct Scope<'scope, 'env: 'scope> {
+    data: Arc<ScopeData>,
+    /// Invariance over `'scope`, so it cannot shrink: `'scope` also bounds every
+    /// `ScopedJoinHandle` returned by `spawn`, and `scope()` only returns once it has confirmed
+    /// (via the park loop) that every such handle's thread has finished, so a handle can't be
+    /// used to access borrowed data after `'scope` ends even if it's never joined explicitly.
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// An owned handle to a scoped thread, returned by `Scope::spawn`
+pub struct ScopedJoinHandle<'scope, T> {
+    inner: JoinHandle<T>,
+    _marker: PhantomData<&'scope ()>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Spawns a new thread within the scope, returning a `ScopedJoinHandle` for it
+    ///
+    /// Like `scoped`, the spawned closure may borrow data owned outside the scope (with the
+    /// `'env` lifetime). Unlike `scoped`, there is no risk of the borrow outliving its owner even
+    /// if a handle is leaked, because `scope` does not return until every thread spawned inside
+    /// it has finished. See `Builder::spawn_scoped` for a named/sized, non-panicking alternative.
+    pub fn spawn<T, F>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T> where
+        T: Send + 'static, F: FnOnce() -> T, F: Send + 'scope
+    {
+        Builder::new().spawn_scoped(self, f).unwrap()
+    }
+}
+
+impl<'scope, T> ScopedJoinHandle<'scope, T> {
+    /// Joins the scoped thread, returning its result
+    ///
+    /// # Panics
+    ///
+    /// `join()` will panic if the owned thread panics
+    pub fn join(self) -> T {
+        let thread = self.thread().clone();
+        match self.try_join() {
+            Ok(res) => res,
+            Err(_) => panic!("child thread {:?} panicked", thread),
+        }
+    }
+
+    /// Joins the scoped thread, returning the child's panic payload rather than panicking
+    pub fn try_join(self) -> thread::Result<T> {
+        self.inner.join()
+    }
+
+    /// Provides the backing `Thread` object
+    pub fn thread(&self) -> &Thread {
+        self.inner.thread()
+    }
+}
+
+/// Creates a scope for spawning threads that may borrow data from outside the scope
+///
+/// Unlike `scoped`/`Builder::scoped`, soundness here does not depend on every `ScopedJoinHandle`
+/// being dropped rather than forgotten: `scope` itself does not return until every thread
+/// spawned within it has finished, so a leaked handle can no longer lead to a dangling borrow.
+///
+/// # Panics
+///
+/// Panics if any of the spawned threads panic.
+pub fn scope<'env, F, R>(f: F) -> R where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> R
+{
+    let scope = Scope {
+        data: Arc::new(ScopeData {
+            num_running_threads: AtomicUsize::new(0),
+            a_thread_panicked: AtomicBool::new(false),
+            main_thread: thread::current(),
+        }),
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    let result = catch_unwind(AssertUnwindSafe(|| f(&scope)));
+
+    while scope.data.num_running_threads.load(Ordering::Acquire) != 0 {
+        thread::park();
+    }
+
+    match result {
+        Err(payload) => resume_unwind(payload),
+        Ok(result) => {
+            if scope.data.a_thread_panicked.load(Ordering::Relaxed) {
+                panic!("a scoped thread panicked");
+            }
+            result
         }
     }
 }
@@ -101,7 +337,7 @@ pub fn scoped<'a, T, F>(f: F) -> JoinGuard<'a, T> where
 #[cfg(test)]
 mod tests {
     use std::thread::sleep_ms;
-    use super::scoped;
+    use super::{scoped, scope, Builder};
 
     #[test]
     fn test_scoped_stack() {
@@ -139,4 +375,59 @@ mod tests {
     fn test_scoped_implicit_panic() {
         let _ = scoped(|| panic!());
     }
+
+    #[test]
+    fn test_builder_scoped_name_and_stack_size() {
+        let guard = Builder::new()
+            .name("builder-worker".to_string())
+            .stack_size(1024 * 1024)
+            .scoped(move || -> String {
+                "Success!".to_string()
+            })
+            .unwrap();
+        assert_eq!(guard.thread().name(), Some("builder-worker"));
+        assert_eq!(guard.join(), "Success!");
+    }
+
+    #[test]
+    fn test_try_join_success() {
+        assert_eq!(scoped(|| 42).try_join().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_try_join_returns_panic_payload() {
+        let err = scoped(|| -> () { panic!("boom") }).try_join().unwrap_err();
+        assert_eq!(*err.downcast::<&'static str>().unwrap(), "boom");
+    }
+
+    #[test]
+    fn test_scope_join_multiple_borrowing_children() {
+        let a = vec![1, 2, 3];
+        let res = scope(|s| {
+            let h1 = s.spawn(|| a[0] + a[1]);
+            let h2 = s.spawn(|| a[2] * 2);
+            h1.join() + h2.join()
+        });
+        assert_eq!(res, 9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scope_propagates_child_panic() {
+        scope(|s| {
+            s.spawn(|| panic!("child"));
+        });
+    }
+
+    #[test]
+    fn test_spawn_scoped_name() {
+        scope(|s| {
+            let handle = Builder::new()
+                .name("scoped-worker".to_string())
+                .spawn_scoped(s, || ())
+                .unwrap();
+            assert_eq!(handle.thread().name(), Some("scoped-worker"));
+            handle.join();
+        });
+    }
 }